@@ -1,19 +1,36 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+// `borsh`/`base64` are only pulled in with their default (`std`-enabled)
+// features in this tree's (nonexistent) Cargo.toml, so the Borsh
+// derive/decode surface stays behind `feature = "std"` the way it always
+// has, rather than being compiled into the wasm/`no_std` contract target.
 #[cfg(feature = "std")]
 use borsh::{BorshDeserialize, BorshSerialize};
 
 use ink_lang as ink;
 
-#[cfg(feature = "std")]
-#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+/// Versioned, Borsh-encoded metadata blob stored base64-encoded on-chain.
+/// `version` allows the format to grow new fields without breaking old blobs.
+#[cfg_attr(feature = "std", derive(BorshSerialize, BorshDeserialize))]
+#[derive(PartialEq, Debug)]
 pub struct UserMetadata {
+    /// Metadata format version, for forward compatibility
+    version: u8,
     /// User INN number
     inn: u64,
+    /// User SNILS number, if known
+    snils: Option<u64>,
+    /// Passport series, if known
+    passport_series: Option<u32>,
+    /// Unix timestamp at which this metadata was issued
+    issued_at: u64,
 }
 
 #[ink::contract]
 mod user_passport {
+    use crate::UserMetadata;
+    #[cfg(feature = "std")]
+    use borsh::BorshDeserialize;
     use ink_prelude::string::String;
     use ink_storage::traits::SpreadAllocate;
 
@@ -29,12 +46,16 @@ mod user_passport {
         birthday: u64,
         /// Counter of user assets
         assets: ink_storage::Mapping<AccountId, u32>,
+        /// Cached sum of all balances in `assets`, kept in sync on deposit/withdraw
+        total_assets: u32,
         /// User sercret metadate: INN, ...
         metadata: String,
         /// Marks client's smart contract as active
         active: bool,
         // Store a contract owner
         owner: AccountId,
+        /// Accounts temporarily authorized to read the metadata / full name
+        disclosed_to: ink_storage::Mapping<AccountId, bool>,
     }
 
     #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
@@ -42,6 +63,45 @@ mod user_passport {
     pub enum Error {
         /// Event emitted when a caller is not a contract owner
         CallerIsNotAnOwner,
+        /// Returned when a caller is neither the owner nor on the metadata disclosure allow-list
+        NotAuthorized,
+        /// Returned when a withdrawal would take an account's balance below zero
+        InsufficientAssets,
+        /// Returned when a deposit would overflow an account's balance or the cached total
+        AssetOverflow,
+        /// Returned when a metadata blob fails to base64-decode or Borsh-deserialize
+        InvalidMetadata,
+    }
+
+    /// Emitted when the contract owner deactivates the passport
+    #[ink(event)]
+    pub struct Deactivated {
+        #[ink(topic)]
+        owner: AccountId,
+    }
+
+    /// Emitted whenever the secret metadata is read
+    #[ink(event)]
+    pub struct MetadataAccessed {
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Emitted whenever an account's asset balance changes
+    #[ink(event)]
+    pub struct AssetChanged {
+        #[ink(topic)]
+        who: AccountId,
+        new_balance: u32,
+    }
+
+    /// Emitted when the contract owner is changed
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
     }
 
     impl UserPassport {
@@ -56,13 +116,15 @@ mod user_passport {
                 contract.active = true;
                 contract.owner = Self::env().caller();
                 // assets are empty initialized
+                contract.total_assets = 0;
             })
         }
 
-        /// Get user name and surname if a caller is a contract owner, else only a name
+        /// Get user name and surname if a caller is the owner or on the disclosure
+        /// allow-list, else only the surname
         #[ink(message)]
         pub fn get_user_name(&self) -> String {
-            if Self::env().caller() == self.owner {
+            if self.is_disclosed(Self::env().caller()) {
                 ink_env::format!("{} {}", &self.surname, &self.name)
             } else {
                 self.surname.clone()
@@ -80,6 +142,7 @@ mod user_passport {
         pub fn deactivate(&mut self) -> Result<(), Error> {
             if Self::env().caller() == self.owner {
                 self.active = false;
+                self.env().emit_event(Deactivated { owner: self.owner });
                 Ok(())
             } else {
                 Err(Error::CallerIsNotAnOwner)
@@ -89,11 +152,132 @@ mod user_passport {
         /// Get user metadata
         #[ink(message)]
         pub fn get_metadata(&self) -> Result<String, Error> {
-            if Self::env().caller() == self.owner {
+            let caller = Self::env().caller();
+            if self.is_disclosed(caller) {
+                self.env().emit_event(MetadataAccessed { by: caller });
                 Ok(self.metadata.clone())
             } else {
-                Err(Error::CallerIsNotAnOwner)
+                Err(Error::NotAuthorized)
+            }
+        }
+
+        /// Validate and store a new base64+Borsh-encoded metadata blob (owner-only)
+        ///
+        /// Full validation (base64-decode + Borsh-deserialize into
+        /// `UserMetadata`) only runs under `feature = "std"`, matching the
+        /// `borsh`/`base64` gating above — this tree has no Cargo.toml wiring
+        /// those crates with `default-features = false` for the wasm target,
+        /// so a real on-chain build only gets the cheap, no_std-safe base64
+        /// alphabet check below until that dependency configuration exists.
+        #[ink(message)]
+        pub fn set_metadata(&mut self, encoded: String) -> Result<(), Error> {
+            if Self::env().caller() != self.owner {
+                return Err(Error::CallerIsNotAnOwner);
+            }
+            #[cfg(feature = "std")]
+            {
+                let decoded = base64::decode(&encoded).map_err(|_| Error::InvalidMetadata)?;
+                UserMetadata::try_from_slice(&decoded).map_err(|_| Error::InvalidMetadata)?;
+            }
+            #[cfg(not(feature = "std"))]
+            {
+                let is_base64_alphabet = encoded
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/' || b == b'=');
+                if encoded.is_empty() || !is_base64_alphabet {
+                    return Err(Error::InvalidMetadata);
+                }
+            }
+            self.metadata = encoded;
+            Ok(())
+        }
+
+        /// Transfer contract ownership to `new_owner`
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), Error> {
+            if Self::env().caller() != self.owner {
+                return Err(Error::CallerIsNotAnOwner);
+            }
+            let previous_owner = self.owner;
+            self.owner = new_owner;
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner,
+            });
+            Ok(())
+        }
+
+        /// Authorize `who` to read the metadata / full name (owner-only)
+        #[ink(message)]
+        pub fn grant_metadata_access(&mut self, who: AccountId) -> Result<(), Error> {
+            if Self::env().caller() != self.owner {
+                return Err(Error::CallerIsNotAnOwner);
+            }
+            self.disclosed_to.insert(&who, &true);
+            Ok(())
+        }
+
+        /// Revoke `who`'s authorization to read the metadata / full name (owner-only)
+        #[ink(message)]
+        pub fn revoke_metadata_access(&mut self, who: AccountId) -> Result<(), Error> {
+            if Self::env().caller() != self.owner {
+                return Err(Error::CallerIsNotAnOwner);
+            }
+            self.disclosed_to.remove(&who);
+            Ok(())
+        }
+
+        /// Whether `who` is the owner or on the metadata disclosure allow-list
+        fn is_disclosed(&self, who: AccountId) -> bool {
+            who == self.owner || self.disclosed_to.get(&who).unwrap_or(false)
+        }
+
+        /// Credit `amount` to `who`'s asset balance
+        #[ink(message)]
+        pub fn deposit_asset(&mut self, who: AccountId, amount: u32) -> Result<(), Error> {
+            if Self::env().caller() != self.owner {
+                return Err(Error::CallerIsNotAnOwner);
             }
+            let balance = self.assets.get(&who).unwrap_or(0);
+            let new_balance = balance.checked_add(amount).ok_or(Error::AssetOverflow)?;
+            self.total_assets = self
+                .total_assets
+                .checked_add(amount)
+                .ok_or(Error::AssetOverflow)?;
+            self.assets.insert(&who, &new_balance);
+            self.env().emit_event(AssetChanged { who, new_balance });
+            Ok(())
+        }
+
+        /// Debit `amount` from `who`'s asset balance
+        #[ink(message)]
+        pub fn withdraw_asset(&mut self, who: AccountId, amount: u32) -> Result<(), Error> {
+            if Self::env().caller() != self.owner {
+                return Err(Error::CallerIsNotAnOwner);
+            }
+            let balance = self.assets.get(&who).unwrap_or(0);
+            let new_balance = balance
+                .checked_sub(amount)
+                .ok_or(Error::InsufficientAssets)?;
+            self.total_assets = self
+                .total_assets
+                .checked_sub(amount)
+                .ok_or(Error::InsufficientAssets)?;
+            self.assets.insert(&who, &new_balance);
+            self.env().emit_event(AssetChanged { who, new_balance });
+            Ok(())
+        }
+
+        /// Get `who`'s current asset balance
+        #[ink(message)]
+        pub fn balance_of(&self, who: AccountId) -> u32 {
+            self.assets.get(&who).unwrap_or(0)
+        }
+
+        /// Get the total amount of assets tracked across all accounts
+        #[ink(message)]
+        pub fn total_assets(&self) -> u32 {
+            self.total_assets
         }
     }
 
@@ -109,12 +293,33 @@ mod user_passport {
         /// Imports `ink_lang` so we can use `#[ink::test]`.
         use ink_lang as ink;
 
-        /// We test a simple use case of our contract.
+        fn default_metadata_encoded() -> String {
+            let metadata = UserMetadata {
+                version: 1,
+                inn: 3664069397,
+                snils: None,
+                passport_series: None,
+                issued_at: 0,
+            };
+            base64::encode(metadata.try_to_vec().unwrap())
+        }
+
+        fn set_caller(caller: ink_env::AccountId) {
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(caller);
+        }
+
+        // No test here drives `ink_env::test::set_block_timestamp`: `UserPassport`
+        // has no lock/unlock or other time-gated logic anywhere in this series for
+        // it to exercise. Add one if such logic is introduced.
+
+        /// We test a simple use case of our contract, driven through the real
+        /// `Self::env().caller()` path via the off-chain test environment.
         #[ink::test]
         fn it_works() {
-            let metadata = UserMetadata { inn: 3664069397 };
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+            set_caller(accounts.alice);
 
-            let metadata_encoded = base64::encode(metadata.try_to_vec().unwrap());
+            let metadata_encoded = default_metadata_encoded();
             println!("base64 encoded metadata: {}", &metadata_encoded);
 
             let mut passport = UserPassport::new(
@@ -125,35 +330,206 @@ mod user_passport {
             );
 
             assert_eq!(passport.get_user_name(), "Иванов Иван");
+            assert_eq!(passport.get_metadata(), Ok(metadata_encoded));
 
-            match passport.get_metadata() {
-                Ok(data) => {
-                    let decoded: Vec<u8> = base64::decode(data).unwrap();
-                    let decoded = UserMetadata::try_from_slice(&decoded).unwrap();
-                    assert_eq!(decoded, metadata)
-                }
-                Err(_) => {
-                    assert!(false, "Metadata should be available");
-                }
-            }
+            let result = passport.deactivate();
+            assert_eq!(result, Ok(()));
+            assert_eq!(passport.is_active(), false);
+        }
+
+        /// Switching the caller to a non-owner should flip the owner-gated
+        /// branches of `get_user_name`, `get_metadata` and `deactivate`.
+        #[ink::test]
+        fn non_owner_cannot_deactivate_or_read_metadata() {
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+            set_caller(accounts.alice);
 
-            let the_owner = passport.owner.to_owned();
+            let mut passport = UserPassport::new(
+                "Иванов".to_owned(),
+                "Иван".to_owned(),
+                503556108,
+                default_metadata_encoded(),
+            );
+
+            set_caller(accounts.bob);
 
-            let array = [0; 32];
-            let account_id: ink_env::AccountId = array.into();
-            passport.owner = account_id;
             assert_eq!(passport.get_user_name(), "Иванов");
+            assert_eq!(passport.get_metadata(), Err(Error::NotAuthorized));
+            assert_eq!(passport.deactivate(), Err(Error::CallerIsNotAnOwner));
+            assert_eq!(passport.is_active(), true);
+        }
 
-            let result = passport.deactivate();
-            assert_eq!(result, Err(Error::CallerIsNotAnOwner));
+        /// Granting a non-owner access to the allow-list should unlock the
+        /// metadata / full-name view for that account only.
+        #[ink::test]
+        fn grant_metadata_access_unlocks_disclosure() {
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+            set_caller(accounts.alice);
+
+            let metadata_encoded = default_metadata_encoded();
+            let mut passport = UserPassport::new(
+                "Иванов".to_owned(),
+                "Иван".to_owned(),
+                503556108,
+                metadata_encoded.clone(),
+            );
 
-            let result = passport.get_metadata();
-            assert_eq!(result, Err(Error::CallerIsNotAnOwner));
+            assert_eq!(passport.grant_metadata_access(accounts.bob), Ok(()));
 
-            passport.owner = the_owner;
-            let result = passport.deactivate();
-            assert_eq!(result, Ok(()));
-            assert_eq!(passport.is_active(), false);
+            set_caller(accounts.bob);
+            assert_eq!(passport.get_user_name(), "Иванов Иван");
+            assert_eq!(passport.get_metadata(), Ok(metadata_encoded));
+
+            set_caller(accounts.alice);
+            assert_eq!(passport.revoke_metadata_access(accounts.bob), Ok(()));
+
+            set_caller(accounts.bob);
+            assert_eq!(passport.get_metadata(), Err(Error::NotAuthorized));
+        }
+
+        /// Deposits and withdrawals should move both the per-account balance and
+        /// the cached `total_assets`, and are gated to the owner.
+        #[ink::test]
+        fn deposit_and_withdraw_asset_track_balance_and_total() {
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+            set_caller(accounts.alice);
+
+            let mut passport = UserPassport::new(
+                "Иванов".to_owned(),
+                "Иван".to_owned(),
+                503556108,
+                default_metadata_encoded(),
+            );
+
+            assert_eq!(passport.deposit_asset(accounts.bob, 10), Ok(()));
+            assert_eq!(passport.balance_of(accounts.bob), 10);
+            assert_eq!(passport.total_assets(), 10);
+
+            assert_eq!(passport.withdraw_asset(accounts.bob, 4), Ok(()));
+            assert_eq!(passport.balance_of(accounts.bob), 6);
+            assert_eq!(passport.total_assets(), 6);
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                passport.deposit_asset(accounts.bob, 1),
+                Err(Error::CallerIsNotAnOwner)
+            );
+        }
+
+        /// Withdrawing more than an account's balance must fail instead of
+        /// underflowing, leaving the balance and total untouched.
+        #[ink::test]
+        fn withdraw_asset_rejects_insufficient_balance() {
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+            set_caller(accounts.alice);
+
+            let mut passport = UserPassport::new(
+                "Иванов".to_owned(),
+                "Иван".to_owned(),
+                503556108,
+                default_metadata_encoded(),
+            );
+
+            assert_eq!(passport.deposit_asset(accounts.bob, 5), Ok(()));
+            assert_eq!(
+                passport.withdraw_asset(accounts.bob, 6),
+                Err(Error::InsufficientAssets)
+            );
+            assert_eq!(passport.balance_of(accounts.bob), 5);
+            assert_eq!(passport.total_assets(), 5);
+        }
+
+        /// A deposit that would overflow an account's balance or the cached
+        /// total must be rejected rather than wrapping.
+        #[ink::test]
+        fn deposit_asset_rejects_overflow() {
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+            set_caller(accounts.alice);
+
+            let mut passport = UserPassport::new(
+                "Иванов".to_owned(),
+                "Иван".to_owned(),
+                503556108,
+                default_metadata_encoded(),
+            );
+
+            assert_eq!(passport.deposit_asset(accounts.bob, u32::MAX), Ok(()));
+            assert_eq!(
+                passport.deposit_asset(accounts.bob, 1),
+                Err(Error::AssetOverflow)
+            );
+            assert_eq!(passport.balance_of(accounts.bob), u32::MAX);
+            assert_eq!(passport.total_assets(), u32::MAX);
+        }
+
+        /// Only the owner can transfer ownership, and the new owner then takes
+        /// over every owner-gated branch.
+        #[ink::test]
+        fn transfer_ownership_moves_owner_gated_access() {
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+            set_caller(accounts.alice);
+
+            let mut passport = UserPassport::new(
+                "Иванов".to_owned(),
+                "Иван".to_owned(),
+                503556108,
+                default_metadata_encoded(),
+            );
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                passport.transfer_ownership(accounts.bob),
+                Err(Error::CallerIsNotAnOwner)
+            );
+
+            set_caller(accounts.alice);
+            assert_eq!(passport.transfer_ownership(accounts.bob), Ok(()));
+            assert_eq!(
+                passport.transfer_ownership(accounts.charlie),
+                Err(Error::CallerIsNotAnOwner)
+            );
+
+            set_caller(accounts.bob);
+            assert_eq!(passport.get_metadata().is_ok(), true);
+            assert_eq!(passport.deactivate(), Ok(()));
+        }
+
+        /// `set_metadata` should accept a valid re-encoded blob and update the
+        /// stored metadata, while rejecting corrupt or non-owner input.
+        #[ink::test]
+        fn set_metadata_validates_and_stores_blob() {
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+            set_caller(accounts.alice);
+
+            let mut passport = UserPassport::new(
+                "Иванов".to_owned(),
+                "Иван".to_owned(),
+                503556108,
+                default_metadata_encoded(),
+            );
+
+            let new_metadata = UserMetadata {
+                version: 1,
+                inn: 1234567890,
+                snils: Some(42),
+                passport_series: Some(1234),
+                issued_at: 1_000_000,
+            };
+            let new_encoded = base64::encode(new_metadata.try_to_vec().unwrap());
+
+            assert_eq!(passport.set_metadata(new_encoded.clone()), Ok(()));
+            assert_eq!(passport.get_metadata(), Ok(new_encoded));
+
+            assert_eq!(
+                passport.set_metadata("not valid base64/borsh".to_owned()),
+                Err(Error::InvalidMetadata)
+            );
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                passport.set_metadata(default_metadata_encoded()),
+                Err(Error::CallerIsNotAnOwner)
+            );
         }
     }
 }
@@ -0,0 +1,136 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod registry {
+    use ink_prelude::string::String;
+    use ink_storage::traits::SpreadAllocate;
+    use user_passport::UserPassportRef;
+
+    /// Passport registry storage: maps a user account to its deployed passport
+    #[ink(storage)]
+    #[derive(SpreadAllocate)]
+    pub struct Registry {
+        /// Code hash of the `UserPassport` contract to instantiate
+        passport_code_hash: Hash,
+        /// User account -> deployed passport contract address
+        passports: ink_storage::Mapping<AccountId, AccountId>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned when the caller already has a passport registered
+        PassportAlreadyExists,
+        /// Returned when the cross-contract instantiation of `UserPassport`, or the
+        /// hand-over of its ownership to the caller, fails
+        InstantiationFailed,
+    }
+
+    /// Emitted when a new passport is deployed and registered for an account
+    #[ink(event)]
+    pub struct PassportCreated {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        passport: AccountId,
+    }
+
+    impl Registry {
+        /// Constructor that sets the code hash new passports are instantiated from
+        #[ink(constructor)]
+        pub fn new(passport_code_hash: Hash) -> Self {
+            ink_lang::utils::initialize_contract(|contract: &mut Self| {
+                contract.passport_code_hash = passport_code_hash;
+            })
+        }
+
+        /// Deploy a new `UserPassport` owned by the caller and register it
+        #[ink(message)]
+        pub fn create_passport(
+            &mut self,
+            surname: String,
+            name: String,
+            birthday: u64,
+            metadata: String,
+        ) -> Result<AccountId, Error> {
+            let caller = Self::env().caller();
+            if self.passports.get(&caller).is_some() {
+                return Err(Error::PassportAlreadyExists);
+            }
+
+            let mut passport = UserPassportRef::new(surname, name, birthday, metadata)
+                .endowment(0)
+                .code_hash(self.passport_code_hash)
+                .salt_bytes(caller.as_ref())
+                .instantiate()
+                .map_err(|_| Error::InstantiationFailed)?;
+            // The passport's constructor records this registry as its owner,
+            // so hand ownership over to the caller.
+            passport
+                .transfer_ownership(caller)
+                .map_err(|_| Error::InstantiationFailed)?;
+            let passport_account = ink_lang::ToAccountId::to_account_id(&passport);
+
+            self.passports.insert(&caller, &passport_account);
+            self.env().emit_event(PassportCreated {
+                owner: caller,
+                passport: passport_account,
+            });
+
+            Ok(passport_account)
+        }
+
+        /// Look up the passport address registered for `who`, if any
+        #[ink(message)]
+        pub fn passport_of(&self, who: AccountId) -> Option<AccountId> {
+            self.passports.get(&who)
+        }
+    }
+
+    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
+    /// module and test functions are marked with a `#[test]` attribute.
+    /// The below code is technically just normal Rust code.
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// Imports `ink_lang` so we can use `#[ink::test]`.
+        use ink_lang as ink;
+
+        /// A caller who already has a registered passport should be rejected
+        /// before any cross-contract instantiation is attempted.
+        #[ink::test]
+        fn create_passport_rejects_existing_owner() {
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(accounts.alice);
+
+            let code_hash = Hash::from([0x42; 32]);
+            let mut registry = Registry::new(code_hash);
+            registry.passports.insert(&accounts.alice, &accounts.bob);
+
+            let result = registry.create_passport(
+                "Иванов".to_owned(),
+                "Иван".to_owned(),
+                503556108,
+                String::new(),
+            );
+            assert_eq!(result, Err(Error::PassportAlreadyExists));
+        }
+
+        /// `passport_of` should reflect what was registered, with no entry for
+        /// accounts that never created one.
+        #[ink::test]
+        fn passport_of_looks_up_registered_passport() {
+            let accounts = ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+            let code_hash = Hash::from([0x42; 32]);
+            let mut registry = Registry::new(code_hash);
+
+            assert_eq!(registry.passport_of(accounts.alice), None);
+
+            registry.passports.insert(&accounts.alice, &accounts.bob);
+            assert_eq!(registry.passport_of(accounts.alice), Some(accounts.bob));
+        }
+    }
+}